@@ -1,17 +1,46 @@
 use std::{
-    sync::{mpsc, Arc, Mutex},
     thread,
     error,
     fmt,
+    panic,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
 };
 
+use crossbeam_channel::{bounded, Receiver, Sender, TrySendError};
+
+/// Default queue capacity, chosen to be large enough that callers relying on
+/// the old unbounded-channel behavior are unlikely to notice the change.
+const DEFAULT_CAPACITY: usize = 8192;
+
+const DEFAULT_THREAD_NAME_PREFIX: &str = "worker";
+
 pub struct ThreadPool {
     workers: Vec<Worker>,
-    sender: mpsc::Sender<Job>,
+    sender: Sender<Message>,
+    overflow_policy: OverflowPolicy,
+    panics_caught: Arc<AtomicUsize>,
 }
 
 type Job = Box<dyn FnOnce() + Send + 'static>;
 
+enum Message {
+    NewJob(Job),
+    Shutdown,
+}
+
+/// Controls what happens to a job submitted via [`ThreadPool::execute`] when
+/// the internal queue is already full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for a free slot in the queue before returning.
+    Block,
+    /// Discard the incoming job and return an error immediately.
+    DropIncoming,
+}
+
 #[derive(Debug)]
 pub enum PoolCreationError {
     InvalidSize,
@@ -27,50 +56,278 @@ impl fmt::Display for PoolCreationError {
 
 impl error::Error for PoolCreationError {}
 
-impl ThreadPool {
-    pub fn build(size: usize) -> Result<ThreadPool, PoolCreationError> {
-        if size <= 0 {
-            return Err(PoolCreationError::InvalidSize);
+/// Why a job submitted via [`ThreadPool::execute`] was not queued.
+#[derive(Debug)]
+pub enum ExecuteError {
+    /// The queue was full and the pool's [`OverflowPolicy::DropIncoming`] rejected the job.
+    QueueFull,
+    /// The pool has been shut down and can no longer accept jobs.
+    Disconnected,
+}
+
+impl fmt::Display for ExecuteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ExecuteError::QueueFull => write!(f, "job queue is full"),
+            ExecuteError::Disconnected => write!(f, "thread pool has been shut down"),
         }
+    }
+}
+
+impl error::Error for ExecuteError {}
+
+/// The outcome of a job submitted via [`ThreadPool::submit`] that a [`JobHandle`] can report.
+#[derive(Debug)]
+pub enum JobError {
+    /// The job panicked while running.
+    Panicked,
+    /// The job was never executed because it could not be queued.
+    Rejected,
+}
+
+impl fmt::Display for JobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            JobError::Panicked => write!(f, "job panicked while running"),
+            JobError::Rejected => write!(f, "job was rejected before it could run"),
+        }
+    }
+}
+
+impl error::Error for JobError {}
+
+/// A handle to a job submitted via [`ThreadPool::submit`], which can be
+/// [`join`](JobHandle::join)ed to block until the job's result is ready.
+pub struct JobHandle<T> {
+    receiver: mpsc::Receiver<Result<T, JobError>>,
+}
+
+impl<T> JobHandle<T> {
+    /// Blocks until the job finishes, returning its value or the reason it
+    /// did not complete.
+    pub fn join(self) -> Result<T, JobError> {
+        self.receiver.recv().unwrap_or(Err(JobError::Rejected))
+    }
+}
+
+/// Builds a [`ThreadPool`] with an extensible set of options, so new knobs
+/// can be added later without breaking existing callers of `ThreadPool::build`.
+pub struct ThreadPoolBuilder {
+    size: usize,
+    capacity: usize,
+    overflow_policy: OverflowPolicy,
+    thread_name_prefix: String,
+}
+
+impl ThreadPoolBuilder {
+    fn new() -> ThreadPoolBuilder {
+        ThreadPoolBuilder {
+            size: 1,
+            capacity: DEFAULT_CAPACITY,
+            overflow_policy: OverflowPolicy::Block,
+            thread_name_prefix: DEFAULT_THREAD_NAME_PREFIX.to_string(),
+        }
+    }
+
+    /// Sets the number of worker threads the pool will spawn.
+    pub fn size(mut self, size: usize) -> ThreadPoolBuilder {
+        self.size = size;
+        self
+    }
+
+    /// Sets the capacity of the internal job queue.
+    pub fn capacity(mut self, capacity: usize) -> ThreadPoolBuilder {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets what happens to a submitted job when the queue is full.
+    pub fn overflow_policy(mut self, overflow_policy: OverflowPolicy) -> ThreadPoolBuilder {
+        self.overflow_policy = overflow_policy;
+        self
+    }
 
-        let (sender, receiver) = mpsc::channel();
+    /// Sets the prefix used when naming each worker's underlying thread.
+    pub fn thread_name_prefix(mut self, prefix: impl Into<String>) -> ThreadPoolBuilder {
+        self.thread_name_prefix = prefix.into();
+        self
+    }
 
-        let receiver = Arc::new(Mutex::new(receiver));
+    pub fn build(self) -> Result<ThreadPool, PoolCreationError> {
+        if self.size == 0 {
+            return Err(PoolCreationError::InvalidSize);
+        }
 
-        let mut workers = Vec::with_capacity(size);
+        let (sender, receiver) = bounded(self.capacity);
+        let panics_caught = Arc::new(AtomicUsize::new(0));
 
-        for id in 0..size {
-            workers.push(Worker::new(id, Arc::clone(&receiver)));
+        let mut workers = Vec::with_capacity(self.size);
+
+        for id in 0..self.size {
+            workers.push(Worker::new(
+                id,
+                receiver.clone(),
+                &self.thread_name_prefix,
+                Arc::clone(&panics_caught),
+            ));
         }
 
-        Ok(ThreadPool { workers, sender })
+        Ok(ThreadPool {
+            workers,
+            sender,
+            overflow_policy: self.overflow_policy,
+            panics_caught,
+        })
+    }
+}
+
+impl ThreadPool {
+    /// Returns a [`ThreadPoolBuilder`] for configuring a pool beyond just its size.
+    pub fn builder() -> ThreadPoolBuilder {
+        ThreadPoolBuilder::new()
     }
 
-    pub fn execute<F>(&self, f: F)
+    pub fn build(size: usize) -> Result<ThreadPool, PoolCreationError> {
+        ThreadPool::builder().size(size).build()
+    }
+
+    /// Queues `f` to run on the pool. What happens when the queue is full is
+    /// governed by the pool's [`OverflowPolicy`]: under `Block` this waits
+    /// for room, under `DropIncoming` it returns an error instead of queuing.
+    pub fn execute<F>(&self, f: F) -> Result<(), ExecuteError>
     where
         F: FnOnce() + Send + 'static,
     {
-        let job = Box::new(f);
+        let message = Message::NewJob(Box::new(f));
 
-        self.sender.send(job).unwrap();
+        match self.overflow_policy {
+            OverflowPolicy::Block => self
+                .sender
+                .send(message)
+                .map_err(|_| ExecuteError::Disconnected),
+            OverflowPolicy::DropIncoming => self.sender.try_send(message).map_err(|err| match err {
+                TrySendError::Full(_) => ExecuteError::QueueFull,
+                TrySendError::Disconnected(_) => ExecuteError::Disconnected,
+            }),
+        }
+    }
+
+    /// Queues `f` to run on the pool and returns a [`JobHandle`] that can be
+    /// joined to retrieve its return value. A panic inside `f` is reported
+    /// through the handle via [`JobError::Panicked`] in addition to being
+    /// logged and counted in [`panics_caught`](ThreadPool::panics_caught),
+    /// same as a panic in a job queued through `execute`.
+    pub fn submit<F, T>(&self, f: F) -> JobHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (result_sender, result_receiver) = mpsc::channel();
+
+        // Report the panic through the handle, then resume unwinding so the
+        // worker's own `catch_unwind` (src/lib.rs) still counts and logs it,
+        // keeping `submit` panics consistent with `execute` panics.
+        let job = move || match panic::catch_unwind(panic::AssertUnwindSafe(f)) {
+            Ok(value) => {
+                let _ = result_sender.send(Ok(value));
+            }
+            Err(cause) => {
+                let _ = result_sender.send(Err(JobError::Panicked));
+                panic::resume_unwind(cause);
+            }
+        };
+
+        let _ = self.execute(job);
+
+        JobHandle {
+            receiver: result_receiver,
+        }
+    }
+
+    /// Returns the number of job panics the pool has caught and recovered from so far.
+    pub fn panics_caught(&self) -> usize {
+        self.panics_caught.load(Ordering::SeqCst)
+    }
+
+    /// Sends a shutdown signal to every worker and blocks until each one
+    /// has exited. The signals are queued behind any jobs already in the
+    /// queue, so every queued job runs before the workers stop.
+    pub fn shutdown(self) {
+        drop(self);
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        for _ in &self.workers {
+            self.sender.send(Message::Shutdown).unwrap();
+        }
+
+        for worker in &mut self.workers {
+            println!("Shutting down worker {}", worker.id);
+
+            if let Some(thread) = worker.thread.take() {
+                thread.join().unwrap();
+            }
+        }
     }
 }
 
 struct Worker {
     id: usize,
-    thread: thread::JoinHandle<()>,
+    thread: Option<thread::JoinHandle<()>>,
 }
 
 impl Worker {
-    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Job>>>) -> Worker {
-        let thread = thread::spawn(move || loop {
-            let job = receiver.lock().unwrap().recv().unwrap();
+    fn new(
+        id: usize,
+        receiver: Receiver<Message>,
+        thread_name_prefix: &str,
+        panics_caught: Arc<AtomicUsize>,
+    ) -> Worker {
+        let thread = thread::Builder::new()
+            .name(format!("{thread_name_prefix}-{id}"))
+            .spawn(move || loop {
+                let message = receiver.recv().unwrap();
+
+                match message {
+                    Message::NewJob(job) => {
+                        println!("Worker {id} got a job; executing.");
+
+                        let outcome = panic::catch_unwind(panic::AssertUnwindSafe(job));
 
-            println!("Worker {id} got a job; executing.");
+                        if let Err(cause) = outcome {
+                            panics_caught.fetch_add(1, Ordering::SeqCst);
+                            eprintln!(
+                                "Worker {id} panicked while running a job: {}",
+                                panic_message(&cause)
+                            );
+                        }
+                    }
+                    Message::Shutdown => {
+                        println!("Worker {id} was told to shut down.");
 
-            job();
-        });
+                        break;
+                    }
+                }
+            })
+            .expect("failed to spawn worker thread");
 
-        Worker { id, thread }
+        Worker {
+            id,
+            thread: Some(thread),
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Extracts a human-readable message from a `catch_unwind` payload, falling
+/// back to a generic description for payloads that aren't a `&str`/`String`.
+fn panic_message(cause: &Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = cause.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = cause.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
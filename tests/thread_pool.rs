@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use rust_webserver::{ExecuteError, JobError, OverflowPolicy, ThreadPool};
+
+#[test]
+fn shutdown_waits_for_all_queued_jobs_to_finish() {
+    let pool = ThreadPool::build(2).unwrap();
+    let completed = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..20 {
+        let completed = Arc::clone(&completed);
+        pool.execute(move || {
+            completed.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    }
+
+    pool.shutdown();
+
+    assert_eq!(completed.load(Ordering::SeqCst), 20);
+}
+
+#[test]
+fn drop_also_waits_for_all_queued_jobs_to_finish() {
+    let completed = Arc::new(AtomicUsize::new(0));
+
+    {
+        let pool = ThreadPool::build(2).unwrap();
+
+        for _ in 0..20 {
+            let completed = Arc::clone(&completed);
+            pool.execute(move || {
+                completed.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+        }
+    }
+
+    assert_eq!(completed.load(Ordering::SeqCst), 20);
+}
+
+#[test]
+fn drop_incoming_rejects_jobs_once_the_queue_is_full() {
+    let pool = ThreadPool::builder()
+        .size(1)
+        .capacity(1)
+        .overflow_policy(OverflowPolicy::DropIncoming)
+        .build()
+        .unwrap();
+
+    // Occupy the single worker so the next job has to sit in the queue.
+    let (started_tx, started_rx) = mpsc::channel::<()>();
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    pool.execute(move || {
+        started_tx.send(()).unwrap();
+        release_rx.recv().unwrap();
+    })
+    .unwrap();
+    started_rx.recv().unwrap();
+
+    // Fills the only queue slot.
+    pool.execute(|| {}).unwrap();
+
+    // Worker is busy and the queue is full: this one must be rejected.
+    let result = pool.execute(|| {});
+    assert!(matches!(result, Err(ExecuteError::QueueFull)));
+
+    release_tx.send(()).unwrap();
+    pool.shutdown();
+}
+
+#[test]
+fn a_panicking_job_does_not_take_down_the_pool() {
+    let pool = ThreadPool::build(1).unwrap();
+
+    pool.execute(|| panic!("boom")).unwrap();
+
+    let ran_after_panic = Arc::new(AtomicUsize::new(0));
+    let ran_after_panic_clone = Arc::clone(&ran_after_panic);
+    pool.execute(move || {
+        ran_after_panic_clone.fetch_add(1, Ordering::SeqCst);
+    })
+    .unwrap();
+
+    pool.shutdown();
+
+    assert_eq!(ran_after_panic.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn submit_returns_the_jobs_value() {
+    let pool = ThreadPool::build(2).unwrap();
+
+    let handle = pool.submit(|| 6 * 7);
+
+    assert_eq!(handle.join().unwrap(), 42);
+}
+
+#[test]
+fn submit_reports_a_panic_through_the_handle_and_counts_it() {
+    let pool = ThreadPool::build(1).unwrap();
+
+    let handle = pool.submit(|| -> i32 { panic!("submitted job exploded") });
+
+    assert!(matches!(handle.join(), Err(JobError::Panicked)));
+
+    // The counter is updated from the worker thread after the result is sent,
+    // so give it a moment to land before asserting on it.
+    for _ in 0..100 {
+        if pool.panics_caught() > 0 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    assert_eq!(pool.panics_caught(), 1);
+}